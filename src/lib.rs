@@ -1,6 +1,17 @@
+#![feature(portable_simd)]
+
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::simd::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use rayon::prelude::*;
+
+/// Letter frequencies packed into a 32-lane SIMD vector. Only the first 26
+/// lanes (a-z) are ever written; lanes 26-31 stay zero padding so they never
+/// affect subset/subtraction comparisons.
+type LetterFreq = u8x32;
 
 // Embed the dictionary at compile time
 const DICTIONARY: &str = include_str!("../dict.txt");
@@ -25,60 +36,63 @@ pub struct AnagramResults {
     results: Vec<String>,
 }
 
-/// Compute letter frequency map for the target phrase
-fn compute_frequency(s: &str) -> [u8; 26] {
-    let mut freq = [0u8; 26];
+/// A bucket of dictionary words that are anagrams of each other - they
+/// share a letter multiset, so letter arithmetic only needs to happen once
+/// per bucket rather than once per word. `representative` is the
+/// alphabetically-first member, used for signature/redundancy checks;
+/// `words` holds every member so final phrases can be expanded later.
+struct DictBucket {
+    representative: String,
+    freq: LetterFreq,
+    len: usize,
+    words: Vec<String>,
+}
+
+/// Compute the packed letter frequency vector for the target phrase
+fn compute_frequency(s: &str) -> LetterFreq {
+    let mut freq = [0u8; 32];
     for b in s.bytes() {
         if b.is_ascii_alphabetic() {
             freq[(b.to_ascii_lowercase() - b'a') as usize] += 1;
         }
     }
-    freq
-}
-
-/// Check if word can be formed from available letters
-#[inline]
-fn can_use_word(word_freq: &[u8; 26], available: &[u8; 26]) -> bool {
-    word_freq
-        .iter()
-        .zip(available.iter())
-        .all(|(need, have)| need <= have)
+    LetterFreq::from_array(freq)
 }
 
-/// Subtract word letters from available letters
+/// Count how many letters a word needs beyond what's available - i.e. how
+/// much of a near-anagram "slack" budget using this word would consume.
+/// Zero means the word is a strict subset of `available`.
 #[inline]
-fn subtract_letters(available: &[u8; 26], word_freq: &[u8; 26]) -> [u8; 26] {
-    let mut result = *available;
-    for i in 0..26 {
-        result[i] -= word_freq[i];
-    }
-    result
+fn word_deficit(word_freq: &LetterFreq, available: &LetterFreq) -> usize {
+    word_freq.saturating_sub(*available).cast::<u16>().reduce_sum() as usize
 }
 
-/// Count total letters remaining
+/// Count total letters remaining. Widen to 16 bits before reducing so the
+/// sum can't overflow a lane for long target phrases.
 #[inline]
-fn count_remaining(freq: &[u8; 26]) -> usize {
-    freq.iter().map(|&c| c as usize).sum()
+fn count_remaining(freq: &LetterFreq) -> usize {
+    freq.cast::<u16>().reduce_sum() as usize
 }
 
 /// Calculate a quality score for an anagram phrase
-/// Higher scores = better (fewer words, longer words, more balanced)
-fn calculate_quality_score(words: &[String]) -> i32 {
-    let num_words = words.len() as i32;
-    let total_len: i32 = words.iter().map(|w| w.len() as i32).sum();
+/// Higher scores = better (fewer words, longer words, more balanced,
+/// less reliance on near-anagram slack)
+fn calculate_quality_score(word_lengths: &[usize], slack_used: usize) -> i32 {
+    let num_words = word_lengths.len() as i32;
+    let total_len: i32 = word_lengths.iter().map(|&l| l as i32).sum();
     let avg_word_len = if num_words > 0 { total_len / num_words } else { 0 };
-    
+
     // Strongly prefer fewer words
     let word_count_penalty = num_words * 1000;
-    
+
     // Reward average word length
     let length_bonus = avg_word_len * 100;
-    
+
     // Small penalty for variance (prefer balanced word lengths)
     let variance_penalty = if num_words > 1 {
-        let variance: i32 = words.iter()
-            .map(|w| {
-                let len = w.len() as i32;
+        let variance: i32 = word_lengths.iter()
+            .map(|&l| {
+                let len = l as i32;
                 (len - avg_word_len).abs()
             })
             .sum();
@@ -86,69 +100,138 @@ fn calculate_quality_score(words: &[String]) -> i32 {
     } else {
         0
     };
-    
-    length_bonus - word_count_penalty - variance_penalty
+
+    // Penalize each leftover/substituted letter so exact anagrams always
+    // outrank near-anagrams that used the same words
+    let slack_penalty = slack_used as i32 * 50;
+
+    length_bonus - word_count_penalty - variance_penalty - slack_penalty
 }
 
-/// Create a canonical signature for a word set to detect redundancy
-fn create_signature(words: &[String]) -> String {
-    let mut substantial: Vec<&String> = words.iter()
+/// Create a canonical signature for a bucket sequence to detect redundancy.
+/// Uses each bucket's representative word, since all members share the
+/// same letters and are interchangeable for this purpose.
+fn create_signature(buckets: &[DictBucket], indices: &[usize]) -> String {
+    let mut substantial: Vec<&str> = indices.iter()
+        .map(|&i| buckets[i].representative.as_str())
         .filter(|w| w.len() >= 4)
         .collect();
     substantial.sort();
-    substantial.iter().map(|s| s.as_str()).collect::<Vec<_>>().join("|")
+    substantial.join("|")
 }
 
-/// Check if adding this word would create a redundant path
+/// Check if adding this bucket would create a redundant path
 fn would_be_redundant(
-    current: &[String],
-    new_word: &str,
+    current: &[usize],
+    new_bucket: usize,
+    buckets: &[DictBucket],
     seen_signatures: &HashSet<String>,
 ) -> bool {
-    let mut test_words = current.to_vec();
-    test_words.push(new_word.to_string());
-    
-    // Create signature from substantial words (4+ letters)
-    let sig = create_signature(&test_words);
-    
+    let mut test_indices = current.to_vec();
+    test_indices.push(new_bucket);
+
+    // Create signature from substantial representatives (4+ letters)
+    let sig = create_signature(buckets, &test_indices);
+
     // If signature is empty (no substantial words yet), not redundant
     if sig.is_empty() {
         return false;
     }
-    
+
     // Check if we've seen this combination of substantial words
     seen_signatures.contains(&sig)
 }
 
-/// Recursively find all anagram combinations, filtering redundancy during search
+/// Recursively find all anagram combinations, filtering redundancy during
+/// search. Operates over anagram-equivalence-class buckets rather than raw
+/// dictionary words: letter arithmetic and pruning happen once per distinct
+/// letter multiset, and `current`/`results` track chosen bucket indices.
+/// Callers expand each index sequence into concrete phrases afterward via
+/// `expand_bucket_combo`.
+///
+/// `slack_remaining` is a near-anagram budget: a word may be used even if
+/// it needs letters the target doesn't supply, as long as the deficit fits
+/// the remaining budget, and a partial match may be accepted once the
+/// letters still owed are themselves within budget (treated as leftovers).
+/// Exact-match callers pass `slack_remaining: 0`, which collapses this back
+/// to the original strict-subset search.
+///
+/// `seen_signatures` and `total_found` are shared across every caller that
+/// searches the same target concurrently (see `find_anagrams_parallel`):
+/// a `Mutex` so cross-branch redundancy pruning still applies when multiple
+/// threads are descending different start buckets, and an `AtomicUsize` so
+/// `max_results` bounds the combined total rather than each branch's own
+/// `results` vec. Sequential callers just pass single-use instances.
+///
+/// `exhaustive` disables the redundancy-signature pruning and the gradual
+/// short-word cutoff, both of which only ever drop words under 4 letters
+/// (`create_signature` ignores them) or de-prioritize short words once
+/// enough results are found. That's the right tradeoff for the heuristic
+/// solvers, but it silently excludes phrases built from short words like
+/// "a"/"to"/"of" - unacceptable for `solve_anagrams_for_hashes`, where a
+/// specific hashed phrase needs to be recoverable rather than merely
+/// represented by one "good enough" phrase per equivalence class.
 fn find_anagrams_recursive(
-    dict_words: &[(String, [u8; 26], usize)],
-    available: &[u8; 26],
-    current: &mut Vec<String>,
-    results: &mut Vec<(i32, Vec<String>)>,
-    seen_signatures: &mut HashSet<String>,
+    buckets: &[DictBucket],
+    available: &LetterFreq,
+    current: &mut Vec<usize>,
+    results: &mut Vec<(i32, Vec<usize>, usize)>,
+    seen_signatures: &Mutex<HashSet<String>>,
+    total_found: &AtomicUsize,
     start_idx: usize,
     remaining_letters: usize,
     max_results: usize,
+    max_words: Option<usize>,
+    max_slack: usize,
+    slack_remaining: usize,
+    exhaustive: bool,
 ) {
     if remaining_letters == 0 {
         // Calculate quality score for this solution
-        let score = calculate_quality_score(current);
-        results.push((score, current.clone()));
-        
+        let lengths: Vec<usize> = current.iter().map(|&i| buckets[i].len).collect();
+        let slack_used = max_slack - slack_remaining;
+        let score = calculate_quality_score(&lengths, slack_used);
+        results.push((score, current.clone(), slack_used));
+        total_found.fetch_add(1, Ordering::Relaxed);
+
         // Record signature to prevent redundant searches
-        let sig = create_signature(current);
-        if !sig.is_empty() {
-            seen_signatures.insert(sig);
+        if !exhaustive {
+            let sig = create_signature(buckets, current);
+            if !sig.is_empty() {
+                seen_signatures.lock().unwrap().insert(sig);
+            }
         }
         return;
     }
 
-    // Stop if we've found enough results
-    if results.len() >= max_results {
+    // Stop if we've found enough results (across every branch sharing this call)
+    if total_found.load(Ordering::Relaxed) >= max_results {
         return;
     }
 
+    // If the letters still owed fit what's left of the slack budget, this
+    // partial word set is itself a valid near-anagram - accept it as a
+    // candidate (treating the shortfall as leftover letters) but keep
+    // searching deeper for better-scoring completions too
+    if remaining_letters <= slack_remaining && !current.is_empty() {
+        let lengths: Vec<usize> = current.iter().map(|&i| buckets[i].len).collect();
+        let slack_used = max_slack - slack_remaining + remaining_letters;
+        let score = calculate_quality_score(&lengths, slack_used);
+        results.push((score, current.clone(), slack_used));
+        total_found.fetch_add(1, Ordering::Relaxed);
+
+        if total_found.load(Ordering::Relaxed) >= max_results {
+            return;
+        }
+    }
+
+    // Stop if this branch has already used as many words as allowed
+    if let Some(max_words) = max_words {
+        if current.len() >= max_words {
+            return;
+        }
+    }
+
     // Dynamic minimum word length based on depth and remaining letters
     let depth = current.len();
     let min_word_len = if depth == 0 {
@@ -165,86 +248,235 @@ fn find_anagrams_recursive(
         (remaining_letters * 6 / 10).max(3).min(remaining_letters)
     };
 
-    // Try words in order (already sorted by length descending)
-    for i in start_idx..dict_words.len() {
-        let (word, word_freq, word_len) = &dict_words[i];
+    // Try buckets in order (already sorted by length descending)
+    for i in start_idx..buckets.len() {
+        let bucket = &buckets[i];
 
-        if *word_len > remaining_letters {
+        // A word is usable if its shortfall against what's available fits
+        // the remaining slack budget (zero shortfall == strict subset)
+        let deficit = word_deficit(&bucket.freq, available);
+        if deficit > slack_remaining {
+            continue;
+        }
+
+        // Only the letters actually drawn from `available` count against
+        // what's left - a word longer than remaining_letters can still fit
+        // if enough of that length is substituted/extra letters the slack
+        // budget (not the target) is covering, e.g. "cats" for target "cat".
+        let drawn = bucket.len - deficit;
+        if drawn > remaining_letters {
             continue;
         }
 
         // Apply minimum word length filter with gradual pruning
-        if *word_len < min_word_len {
+        if !exhaustive && bucket.len < min_word_len {
             // Only explore smaller words if we haven't found many results yet
             // or if remaining letters is very small
-            if results.len() > max_results / 10 && remaining_letters > 5 {
+            if total_found.load(Ordering::Relaxed) > max_results / 10 && remaining_letters > 5 {
                 break; // Skip rest since they're even shorter
             }
         }
 
-        if !can_use_word(word_freq, available) {
-            continue;
-        }
-
         // Skip if this would create a redundant path
-        if would_be_redundant(current, word, seen_signatures) {
+        if !exhaustive && would_be_redundant(current, i, buckets, &seen_signatures.lock().unwrap()) {
             continue;
         }
 
-        let new_available = subtract_letters(available, word_freq);
-        let new_remaining = remaining_letters - word_len;
-        
-        current.push(word.clone());
-        
+        let new_available = available.saturating_sub(bucket.freq);
+        let new_remaining = remaining_letters - drawn;
+        let new_slack_remaining = slack_remaining - deficit;
+
+        current.push(i);
+
         // Recurse with priority: longer words are tried first
         find_anagrams_recursive(
-            dict_words,
+            buckets,
             &new_available,
             current,
             results,
             seen_signatures,
+            total_found,
             i,
             new_remaining,
             max_results,
+            max_words,
+            max_slack,
+            new_slack_remaining,
+            exhaustive,
         );
-        
+
         current.pop();
-        
+
         // Early exit if we've found enough results
-        if results.len() >= max_results {
+        if total_found.load(Ordering::Relaxed) >= max_results {
             return;
         }
     }
 }
 
-#[wasm_bindgen]
-pub fn test_logging() {
-    debug_log!("Debug logging is ENABLED");
-    #[cfg(feature = "debug")]
-    web_sys::console::log_1(&"Direct console.log test".into());
+/// Run the depth-0 fan-out of the search in parallel. Each top-level bucket
+/// choice is an independent subtree with its own available-letters vector,
+/// so buckets are partitioned across worker threads via rayon (native) /
+/// wasm-bindgen-rayon (wasm, once the host page has spun up the thread
+/// pool). `seen_signatures` and the result count are shared across every
+/// branch (a `Mutex`-guarded set and an `AtomicUsize` respectively) so
+/// cross-branch redundancy pruning and the `max_results` cap both apply to
+/// the combined search rather than per-branch. Which signatures are already
+/// recorded when a branch checks them depends on thread scheduling, and the
+/// depth-0 bucket itself gets no redundancy check, so the set of results can
+/// differ from a single-threaded run. Without the shared state a dictionary with many valid
+/// first-word buckets could hold `max_results` worth of tuples *per
+/// branch* in memory before the merge.
+fn find_anagrams_parallel(
+    buckets: &[DictBucket],
+    target_freq: &LetterFreq,
+    target_len: usize,
+    max_results: usize,
+) -> Vec<(i32, Vec<usize>, usize)> {
+    let seen_signatures = Mutex::new(HashSet::new());
+    let total_found = AtomicUsize::new(0);
+
+    buckets
+        .par_iter()
+        .enumerate()
+        .map(|(i, bucket)| {
+            let mut branch_results = Vec::new();
+
+            if total_found.load(Ordering::Relaxed) >= max_results {
+                return branch_results;
+            }
+            if bucket.len > target_len {
+                return branch_results;
+            }
+            if word_deficit(&bucket.freq, target_freq) > 0 {
+                return branch_results;
+            }
+
+            let new_available = target_freq.saturating_sub(bucket.freq);
+            let new_remaining = target_len - bucket.len;
+            let mut current = vec![i];
+
+            find_anagrams_recursive(
+                buckets,
+                &new_available,
+                &mut current,
+                &mut branch_results,
+                &seen_signatures,
+                &total_found,
+                i,
+                new_remaining,
+                max_results,
+                None,
+                0,
+                0,
+                false,
+            );
+
+            branch_results
+        })
+        .reduce(Vec::new, |mut acc, mut branch| {
+            acc.append(&mut branch);
+            acc
+        })
 }
 
-#[wasm_bindgen]
-pub fn solve_anagrams(target: &str) -> Result<JsValue, JsValue> {
-    debug_log!("=== Starting anagram solver ===");
-    debug_log!("Target phrase: '{}'", target);
-    
+/// Expand a chosen sequence of bucket indices into every concrete phrase
+/// formed by the Cartesian product of each bucket's member words.
+fn expand_bucket_combo(buckets: &[DictBucket], indices: &[usize]) -> Vec<Vec<String>> {
+    let mut phrases: Vec<Vec<String>> = vec![Vec::new()];
+    for &idx in indices {
+        let words = &buckets[idx].words;
+        let mut next = Vec::with_capacity(phrases.len() * words.len());
+        for phrase in &phrases {
+            for word in words {
+                let mut expanded = phrase.clone();
+                expanded.push(word.clone());
+                next.push(expanded);
+            }
+        }
+        phrases = next;
+    }
+    phrases
+}
+
+/// Caller-configurable search limits for `solve_anagrams_with_options`,
+/// replacing the fixed `max_results` and final result caps the other solver
+/// entry points bake in. `min_word_len` filters the dictionary up front; the
+/// depth-based short-word cutoff inside the search still applies.
+#[derive(Serialize, Deserialize)]
+pub struct SolverOptions {
+    max_words: Option<usize>,
+    min_word_len: usize,
+    max_results: usize,
+    forbidden_words: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HashMatch {
+    phrase: String,
+    hash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HashMatchResults {
+    matches: Vec<HashMatch>,
+}
+
+/// A near-anagram phrase, reporting how many letters it left over or
+/// substituted relative to the target
+#[derive(Serialize, Deserialize)]
+pub struct NearAnagramMatch {
+    phrase: String,
+    slack_used: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NearAnagramResults {
+    results: Vec<NearAnagramMatch>,
+}
+
+/// Parse the embedded dictionary and pre-filter/sort it against a target
+/// phrase, collapsing anagram-sibling words (e.g. "listen"/"silent") into
+/// shared buckets. Shared by every solver entry point so the parsing,
+/// validation and debug logging stay in one place.
+fn prepare_dictionary(
+    target: &str,
+) -> Result<(Vec<DictBucket>, LetterFreq, usize), JsValue> {
+    prepare_dictionary_filtered(target, 1, &HashSet::new(), 0)
+}
+
+/// Like `prepare_dictionary`, but also drops words shorter than
+/// `min_word_len` and any word in `forbidden` before bucketing, and admits
+/// words needing up to `max_slack` letters beyond what the target supplies
+/// (0 reproduces the original strict-subset filter). Used by
+/// `solve_anagrams_with_options` and `solve_near_anagrams`.
+fn prepare_dictionary_filtered(
+    target: &str,
+    min_word_len: usize,
+    forbidden: &HashSet<String>,
+    max_slack: usize,
+) -> Result<(Vec<DictBucket>, LetterFreq, usize), JsValue> {
     // Validate input
     if target.trim().is_empty() {
         debug_log!("ERROR: Empty target phrase");
         return Err(JsValue::from_str("Target phrase cannot be empty"));
     }
-    
+
     debug_log!("Parsing dictionary...");
     // Parse embedded dictionary
     let dictionary: Vec<String> = DICTIONARY
         .lines()
         .map(|s| s.trim().to_lowercase())
-        .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphabetic()))
+        .filter(|s| {
+            !s.is_empty()
+                && s.len() >= min_word_len
+                && !forbidden.contains(s)
+                && s.chars().all(|c| c.is_ascii_alphabetic())
+        })
         .collect();
-    
+
     debug_log!("Dictionary size: {} words", dictionary.len());
-    
+
     if dictionary.is_empty() {
         debug_log!("ERROR: Dictionary is empty");
         return Err(JsValue::from_str("Dictionary is empty - check dict.txt"));
@@ -258,66 +490,323 @@ pub fn solve_anagrams(target: &str) -> Result<JsValue, JsValue> {
 
     // Pre-process dictionary
     debug_log!("Pre-processing dictionary...");
-    let mut dict_words: Vec<(String, [u8; 26], usize)> = dictionary
+    let dict_words: Vec<(String, LetterFreq, usize)> = dictionary
         .into_iter()
         .map(|w| {
             let freq = compute_frequency(&w);
             let len = count_remaining(&freq);
             (w, freq, len)
         })
-        .filter(|(_, freq, len)| *len <= target_len && can_use_word(freq, &target_freq))
+        // A word up to `max_slack` letters longer than the target can still
+        // be usable if the extra length is covered by slack (substituted
+        // letters), so the length bound must widen by the same budget.
+        .filter(|(_, freq, len)| *len <= target_len + max_slack && word_deficit(freq, &target_freq) <= max_slack)
         .collect();
 
     debug_log!("Filtered dictionary: {} valid words", dict_words.len());
 
+    // Group words into anagram-equivalence-class buckets, keyed by their
+    // canonical sorted-letter signature
+    let mut grouped: std::collections::HashMap<String, (LetterFreq, usize, Vec<String>)> =
+        std::collections::HashMap::new();
+    for (word, freq, len) in dict_words {
+        let mut letters: Vec<u8> = word.bytes().collect();
+        letters.sort_unstable();
+        let key = String::from_utf8(letters).unwrap();
+
+        grouped.entry(key)
+            .or_insert_with(|| (freq, len, Vec::new()))
+            .2
+            .push(word);
+    }
+
+    let mut buckets: Vec<DictBucket> = grouped
+        .into_values()
+        .map(|(freq, len, mut words)| {
+            words.sort();
+            let representative = words[0].clone();
+            DictBucket { representative, freq, len, words }
+        })
+        .collect();
+
+    debug_log!("Collapsed into {} anagram-equivalence buckets", buckets.len());
+
     // Sort by length descending - ensures we try longer words first
-    dict_words.sort_by(|a, b| b.2.cmp(&a.2));
+    buckets.sort_by(|a, b| b.len.cmp(&a.len));
+
+    if !buckets.is_empty() {
+        debug_log!("Longest bucket: '{}' ({} letters)", buckets[0].representative, buckets[0].len);
+        debug_log!("Shortest bucket: '{}' ({} letters)",
+                   buckets[buckets.len()-1].representative,
+                   buckets[buckets.len()-1].len);
+    }
+
+    Ok((buckets, target_freq, target_len))
+}
+
+/// Compute the lowercase hex MD5 digest of a phrase
+fn md5_hex(phrase: &str) -> String {
+    format!("{:x}", md5::compute(phrase.as_bytes()))
+}
+
+/// Upper bound on the word count `permute_words` will be handed. Permutation
+/// count grows factorially, so a solution with more words than this would
+/// build a prohibitively large result vec for a single phrase; callers fall
+/// back to the canonical word order instead of permuting past this point.
+const MAX_PERMUTE_WORDS: usize = 8;
+
+/// Generate every word-order permutation of a word set. Used to test MD5
+/// variants, since the hash depends on the order the words are joined in.
+/// Callers must keep `words.len()` within `MAX_PERMUTE_WORDS` - this does
+/// not itself enforce the cap, since n! would already be computed before a
+/// check inside the recursion could bail out.
+fn permute_words(words: &[String]) -> Vec<Vec<String>> {
+    if words.len() <= 1 {
+        return vec![words.to_vec()];
+    }
+
+    let mut result = Vec::new();
+    for i in 0..words.len() {
+        let mut rest = words.to_vec();
+        let chosen = rest.remove(i);
+        for mut perm in permute_words(&rest) {
+            perm.insert(0, chosen.clone());
+            result.push(perm);
+        }
+    }
+    result
+}
+
+#[wasm_bindgen]
+pub fn test_logging() {
+    debug_log!("Debug logging is ENABLED");
+    #[cfg(feature = "debug")]
+    web_sys::console::log_1(&"Direct console.log test".into());
+}
+
+#[wasm_bindgen]
+pub fn solve_anagrams(target: &str) -> Result<JsValue, JsValue> {
+    debug_log!("=== Starting anagram solver ===");
+    debug_log!("Target phrase: '{}'", target);
+
+    let (buckets, target_freq, target_len) = prepare_dictionary(target)?;
+
+    // Fan the search out across depth-0 bucket choices, one worker per
+    // initial word, then merge before ranking
+    debug_log!("Starting parallel recursive search...");
+    let mut results = find_anagrams_parallel(&buckets, &target_freq, target_len, 50_000);
+
+    debug_log!("Search complete. Found {} bucket-level solutions", results.len());
+
+    // Sort by quality score (highest first)
+    debug_log!("Sorting by quality score...");
+    results.sort_by(|a, b| b.0.cmp(&a.0));
+
+    // Expand each bucket sequence into concrete phrases, with final dedup
+    debug_log!("Expanding bucket combinations and deduplicating...");
+    let mut seen_phrases = HashSet::new();
+    let mut all_anagrams: Vec<String> = Vec::new();
+    'expand: for (_, indices, _) in &results {
+        for words in expand_bucket_combo(&buckets, indices) {
+            let phrase = words.join(" ");
+            if seen_phrases.insert(phrase.clone()) {
+                all_anagrams.push(phrase);
+                if all_anagrams.len() >= 10_000 {
+                    break 'expand;
+                }
+            }
+        }
+    }
+
+    debug_log!("Final result count: {}", all_anagrams.len());
+    if !all_anagrams.is_empty() {
+        debug_log!("Best result: '{}'", all_anagrams[0]);
+    }
+
+    let anagram_results = AnagramResults {
+        results: all_anagrams,
+    };
+
+    debug_log!("Serializing results...");
+    let result = serde_wasm_bindgen::to_value(&anagram_results)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)));
     
-    if !dict_words.is_empty() {
-        debug_log!("Longest word: '{}' ({} letters)", dict_words[0].0, dict_words[0].2);
-        debug_log!("Shortest word: '{}' ({} letters)", 
-                   dict_words[dict_words.len()-1].0, 
-                   dict_words[dict_words.len()-1].2);
+    debug_log!("=== Anagram solver complete ===");
+    result
+}
+
+/// Crack a set of MD5-hashed phrases by finding anagrams of `target` whose
+/// space-joined, word-ordered rendering matches one of `hashes_hex`.
+///
+/// MD5 depends on exact word order and spacing, so for every complete
+/// letter-multiset solution we try every permutation of its words rather
+/// than just the single canonical ordering `solve_anagrams` returns.
+/// Phrases with more than `MAX_PERMUTE_WORDS` words are the exception: only
+/// their canonical ordering is hashed, so other orderings of them are not
+/// matched.
+#[wasm_bindgen]
+pub fn solve_anagrams_for_hashes(target: &str, hashes_hex: Vec<String>) -> Result<JsValue, JsValue> {
+    debug_log!("=== Starting hash-targeted anagram solver ===");
+    debug_log!("Target phrase: '{}'", target);
+
+    if hashes_hex.is_empty() {
+        debug_log!("ERROR: No target hashes supplied");
+        return Err(JsValue::from_str("At least one target hash is required"));
     }
 
-    // Find anagrams with inline redundancy filtering
-    debug_log!("Starting recursive search...");
+    let target_hashes: HashSet<String> = hashes_hex.iter().map(|h| h.to_lowercase()).collect();
+    debug_log!("Target hash count: {}", target_hashes.len());
+
+    let (buckets, target_freq, target_len) = prepare_dictionary(target)?;
+
+    debug_log!("Searching for bucket-level solutions...");
+    let mut word_sets = Vec::new();
+    let mut current = Vec::new();
+    let seen_signatures = Mutex::new(HashSet::new());
+    let total_found = AtomicUsize::new(0);
+
+    // Run exhaustively: the heuristic redundancy/short-word pruning is tuned
+    // to show one "good enough" phrase per near-equivalent combination, but
+    // the hashed phrase being cracked might be exactly one of the ones that
+    // pruning would drop (e.g. it relies on "a"/"to"/"of").
+    find_anagrams_recursive(
+        &buckets,
+        &target_freq,
+        &mut current,
+        &mut word_sets,
+        &seen_signatures,
+        &total_found,
+        0,
+        target_len,
+        50_000,
+        None,
+        0,
+        0,
+        true,
+    );
+
+    debug_log!("Found {} bucket-level solutions, testing permutations against {} target hashes",
+               word_sets.len(), target_hashes.len());
+
+    let mut matches = Vec::new();
+    let mut matched_hashes: HashSet<String> = HashSet::new();
+
+    'search: for (_, indices, _) in &word_sets {
+        for words in expand_bucket_combo(&buckets, indices) {
+            // A phrase with more words than MAX_PERMUTE_WORDS would make
+            // permute_words build a factorially large vec for this one
+            // phrase - only its canonical ordering is tried past that point.
+            if words.len() > MAX_PERMUTE_WORDS {
+                debug_log!("Skipping permutations for a {}-word phrase (exceeds cap)", words.len());
+                let phrase = words.join(" ");
+                let hash = md5_hex(&phrase);
+
+                if target_hashes.contains(&hash) && matched_hashes.insert(hash.clone()) {
+                    debug_log!("Match found: '{}' -> {}", phrase, hash);
+                    matches.push(HashMatch { phrase, hash });
+
+                    if matched_hashes.len() == target_hashes.len() {
+                        break 'search;
+                    }
+                }
+                continue;
+            }
+
+            for perm in permute_words(&words) {
+                let phrase = perm.join(" ");
+                let hash = md5_hex(&phrase);
+
+                if target_hashes.contains(&hash) && matched_hashes.insert(hash.clone()) {
+                    debug_log!("Match found: '{}' -> {}", phrase, hash);
+                    matches.push(HashMatch { phrase, hash });
+
+                    // Short-circuit once every requested hash has a hit
+                    if matched_hashes.len() == target_hashes.len() {
+                        break 'search;
+                    }
+                }
+            }
+        }
+    }
+
+    debug_log!("Hash search complete. {} of {} hashes matched",
+               matched_hashes.len(), target_hashes.len());
+
+    let hash_results = HashMatchResults { matches };
+
+    debug_log!("Serializing results...");
+    let result = serde_wasm_bindgen::to_value(&hash_results)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)));
+
+    debug_log!("=== Hash-targeted anagram solver complete ===");
+    result
+}
+
+/// Like `solve_anagrams`, but with search behavior driven by a caller-
+/// supplied `SolverOptions` instead of the fixed `max_results` and final
+/// result caps: a max words-per-phrase bound, a minimum dictionary word
+/// length, a result cap, and a forbidden-word list. The depth-based
+/// short-word cutoff and redundancy pruning of `find_anagrams_recursive`
+/// still apply.
+#[wasm_bindgen]
+pub fn solve_anagrams_with_options(target: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    debug_log!("=== Starting configurable anagram solver ===");
+    debug_log!("Target phrase: '{}'", target);
+
+    let options: SolverOptions = serde_wasm_bindgen::from_value(options)
+        .map_err(|e| JsValue::from_str(&format!("Invalid solver options: {}", e)))?;
+
+    let forbidden: HashSet<String> = options.forbidden_words.iter()
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    let (buckets, target_freq, target_len) =
+        prepare_dictionary_filtered(target, options.min_word_len, &forbidden, 0)?;
+
+    debug_log!("Starting recursive search (max_words={:?}, max_results={})",
+               options.max_words, options.max_results);
     let mut results = Vec::new();
     let mut current = Vec::new();
-    let mut seen_signatures = HashSet::new();
-    
+    let seen_signatures = Mutex::new(HashSet::new());
+    let total_found = AtomicUsize::new(0);
+
     find_anagrams_recursive(
-        &dict_words,
+        &buckets,
         &target_freq,
         &mut current,
         &mut results,
-        &mut seen_signatures,
+        &seen_signatures,
+        &total_found,
         0,
         target_len,
-        50_000,
+        options.max_results,
+        options.max_words,
+        0,
+        0,
+        false,
     );
 
-    debug_log!("Search complete. Found {} solutions", results.len());
+    debug_log!("Search complete. Found {} bucket-level solutions", results.len());
 
     // Sort by quality score (highest first)
     debug_log!("Sorting by quality score...");
     results.sort_by(|a, b| b.0.cmp(&a.0));
 
-    // Convert to strings with final deduplication
-    debug_log!("Converting to strings and deduplicating...");
+    // Expand each bucket sequence into concrete phrases, with final dedup
+    debug_log!("Expanding bucket combinations and deduplicating...");
     let mut seen_phrases = HashSet::new();
-    let all_anagrams: Vec<String> = results
-        .into_iter()
-        .filter_map(|(_, words)| {
+    let mut all_anagrams: Vec<String> = Vec::new();
+    'expand: for (_, indices, _) in &results {
+        for words in expand_bucket_combo(&buckets, indices) {
             let phrase = words.join(" ");
             if seen_phrases.insert(phrase.clone()) {
-                Some(phrase)
-            } else {
-                None
+                all_anagrams.push(phrase);
+                if all_anagrams.len() >= options.max_results {
+                    break 'expand;
+                }
             }
-        })
-        .take(10_000)
-        .collect();
+        }
+    }
 
     debug_log!("Final result count: {}", all_anagrams.len());
     if !all_anagrams.is_empty() {
@@ -331,7 +820,222 @@ pub fn solve_anagrams(target: &str) -> Result<JsValue, JsValue> {
     debug_log!("Serializing results...");
     let result = serde_wasm_bindgen::to_value(&anagram_results)
         .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)));
-    
-    debug_log!("=== Anagram solver complete ===");
+
+    debug_log!("=== Configurable anagram solver complete ===");
     result
 }
+
+/// Find phrases that are anagrams of `target` to within `max_slack`
+/// leftover or substituted letters - useful when the target has a stray
+/// letter, or the caller just wants looser matches than an exact anagram.
+#[wasm_bindgen]
+pub fn solve_near_anagrams(target: &str, max_slack: usize) -> Result<JsValue, JsValue> {
+    debug_log!("=== Starting near-anagram solver (max_slack={}) ===", max_slack);
+    debug_log!("Target phrase: '{}'", target);
+
+    let (buckets, target_freq, target_len) =
+        prepare_dictionary_filtered(target, 1, &HashSet::new(), max_slack)?;
+
+    debug_log!("Starting recursive search...");
+    let mut results = Vec::new();
+    let mut current = Vec::new();
+    let seen_signatures = Mutex::new(HashSet::new());
+    let total_found = AtomicUsize::new(0);
+
+    find_anagrams_recursive(
+        &buckets,
+        &target_freq,
+        &mut current,
+        &mut results,
+        &seen_signatures,
+        &total_found,
+        0,
+        target_len,
+        50_000,
+        None,
+        max_slack,
+        max_slack,
+        false,
+    );
+
+    debug_log!("Search complete. Found {} bucket-level solutions", results.len());
+
+    // Sort by quality score (highest first) - exact matches outrank
+    // near-matches that used the same words, via the slack penalty
+    debug_log!("Sorting by quality score...");
+    results.sort_by(|a, b| b.0.cmp(&a.0));
+
+    // Expand each bucket sequence into concrete phrases, with final dedup
+    debug_log!("Expanding bucket combinations and deduplicating...");
+    let mut seen_phrases = HashSet::new();
+    let mut near_matches: Vec<NearAnagramMatch> = Vec::new();
+    'expand: for (_, indices, slack_used) in &results {
+        for words in expand_bucket_combo(&buckets, indices) {
+            let phrase = words.join(" ");
+            if seen_phrases.insert(phrase.clone()) {
+                near_matches.push(NearAnagramMatch {
+                    phrase,
+                    slack_used: *slack_used,
+                });
+                if near_matches.len() >= 10_000 {
+                    break 'expand;
+                }
+            }
+        }
+    }
+
+    debug_log!("Final result count: {}", near_matches.len());
+
+    let near_results = NearAnagramResults {
+        results: near_matches,
+    };
+
+    debug_log!("Serializing results...");
+    let result = serde_wasm_bindgen::to_value(&near_results)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)));
+
+    debug_log!("=== Near-anagram solver complete ===");
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_frequency_counts_letters_case_insensitively() {
+        let freq = compute_frequency("Cat").to_array();
+        assert_eq!(freq[(b'c' - b'a') as usize], 1);
+        assert_eq!(freq[(b'a' - b'a') as usize], 1);
+        assert_eq!(freq[(b't' - b'a') as usize], 1);
+        assert_eq!(count_remaining(&compute_frequency("Cat")), 3);
+    }
+
+    #[test]
+    fn compute_frequency_ignores_non_alphabetic_bytes() {
+        let freq = compute_frequency("a-b 2c!");
+        assert_eq!(count_remaining(&freq), 3);
+    }
+
+    #[test]
+    fn word_deficit_is_zero_for_a_strict_subset() {
+        let available = compute_frequency("cat");
+        let word = compute_frequency("cat");
+        assert_eq!(word_deficit(&word, &available), 0);
+    }
+
+    #[test]
+    fn word_deficit_counts_each_missing_letter() {
+        let available = compute_frequency("cat");
+        // "cab" needs a 'b' the target doesn't supply
+        let word = compute_frequency("cab");
+        assert_eq!(word_deficit(&word, &available), 1);
+    }
+
+    #[test]
+    fn word_deficit_counts_extra_copies_of_a_shared_letter() {
+        let available = compute_frequency("cat");
+        // "tat" needs two 't's but "cat" only supplies one
+        let word = compute_frequency("tat");
+        assert_eq!(word_deficit(&word, &available), 1);
+    }
+
+    fn bucket(words: &[&str]) -> DictBucket {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        let freq = compute_frequency(&words[0]);
+        let len = count_remaining(&freq);
+        DictBucket {
+            representative: words[0].clone(),
+            freq,
+            len,
+            words,
+        }
+    }
+
+    #[test]
+    fn expand_bucket_combo_is_the_cartesian_product_of_bucket_members() {
+        let buckets = vec![
+            bucket(&["listen", "silent"]),
+            bucket(&["cat"]),
+        ];
+
+        let mut phrases: Vec<String> = expand_bucket_combo(&buckets, &[0, 1])
+            .into_iter()
+            .map(|words| words.join(" "))
+            .collect();
+        phrases.sort();
+
+        assert_eq!(phrases, vec!["listen cat".to_string(), "silent cat".to_string()]);
+    }
+
+    #[test]
+    fn expand_bucket_combo_of_no_indices_yields_one_empty_phrase() {
+        let buckets: Vec<DictBucket> = vec![];
+        let phrases = expand_bucket_combo(&buckets, &[]);
+        assert_eq!(phrases, vec![Vec::<String>::new()]);
+    }
+
+    fn search(
+        buckets: &[DictBucket],
+        target: &str,
+        max_slack: usize,
+    ) -> Vec<(i32, Vec<usize>, usize)> {
+        let target_freq = compute_frequency(target);
+        let target_len = count_remaining(&target_freq);
+        let mut results = Vec::new();
+        let mut current = Vec::new();
+        let seen_signatures = Mutex::new(HashSet::new());
+        let total_found = AtomicUsize::new(0);
+
+        find_anagrams_recursive(
+            buckets,
+            &target_freq,
+            &mut current,
+            &mut results,
+            &seen_signatures,
+            &total_found,
+            0,
+            target_len,
+            50_000,
+            None,
+            max_slack,
+            max_slack,
+            false,
+        );
+
+        results
+    }
+
+    // "cat" -> "cab" is distance 2 (leftover 't' and substituted 'b'), not 1:
+    // a budget of 1 must not be enough to accept it.
+    #[test]
+    fn near_anagram_rejects_a_word_whose_true_distance_exceeds_the_slack_budget() {
+        let buckets = vec![bucket(&["cab"])];
+        assert!(search(&buckets, "cat", 1).is_empty());
+    }
+
+    #[test]
+    fn near_anagram_accepts_a_word_whose_true_distance_fits_the_slack_budget() {
+        let buckets = vec![bucket(&["cab"])];
+        let results = search(&buckets, "cat", 2);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].2, 2);
+    }
+
+    // "cats" is longer than target "cat" because it substitutes in an 's',
+    // not because it leaves letters over - this must be charged as slack 1,
+    // not rejected by a length guard that only accounts for leftovers.
+    #[test]
+    fn near_anagram_accepts_a_word_longer_than_the_target_via_substitution() {
+        let buckets = vec![bucket(&["cats"])];
+        let results = search(&buckets, "cat", 1);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].2, 1);
+    }
+
+    #[test]
+    fn near_anagram_rejects_a_substituted_word_with_no_slack_budget() {
+        let buckets = vec![bucket(&["cats"])];
+        assert!(search(&buckets, "cat", 0).is_empty());
+    }
+}